@@ -0,0 +1,125 @@
+//! Per-agent lifecycle tracking. Replaces the single `clustering_status`
+//! string with small per-agent state machines the frontend can observe and
+//! query directly.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter};
+
+/// The lifecycle phase of a single agent.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum AgentLifecycle {
+    Idle,
+    Initializing,
+    Analyzing,
+    Responding,
+    Error { reason: String },
+}
+
+/// One agent's current lifecycle phase plus identifying metadata, as
+/// returned by `get_agent_states`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AgentState {
+    pub name: String,
+    pub specialization: String,
+    pub state: AgentLifecycle,
+    /// Unix timestamp (seconds) of the last transition.
+    pub since: f64,
+}
+
+/// Payload of the `agent_state_changed` event, emitted on every transition.
+#[derive(Debug, Clone, Serialize)]
+struct AgentStateChanged {
+    name: String,
+    state: AgentLifecycle,
+    since: f64,
+}
+
+/// Tracks every known agent's lifecycle phase behind a single mutex. Agent
+/// counts are small (a handful), so one lock is simpler than sharding and
+/// isn't a contention point.
+///
+/// `agents` holds real, queryable agents as reported by `get_agent_states`.
+/// `dispatch` is separate bookkeeping for "a request is in flight but we
+/// don't know which agent will end up handling it yet" (e.g. while waiting
+/// on the sidecar to route a chat message or run clustering) — it's keyed by
+/// routing stage, not by agent, and must never leak into `agents`/`snapshot`.
+pub struct AgentRegistry {
+    agents: Mutex<HashMap<String, AgentState>>,
+    dispatch: Mutex<HashMap<String, AgentLifecycle>>,
+}
+
+impl AgentRegistry {
+    pub fn new() -> Self {
+        Self {
+            agents: Mutex::new(HashMap::new()),
+            dispatch: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Returns every known agent's current state, sorted by name. Never
+    /// includes in-flight dispatch bookkeeping (see [`Self::dispatch`]).
+    pub fn snapshot(&self) -> Vec<AgentState> {
+        let mut states: Vec<_> = self.agents.lock().unwrap().values().cloned().collect();
+        states.sort_by(|a, b| a.name.cmp(&b.name));
+        states
+    }
+
+    /// Transitions `name` to `state` (registering it with `specialization`
+    /// the first time it's seen) and emits `agent_state_changed`. `name`
+    /// must identify a real agent — use [`Self::dispatch`] for in-flight
+    /// routing status that shouldn't show up in `get_agent_states`.
+    pub fn transition(&self, app: &AppHandle, name: &str, specialization: &str, state: AgentLifecycle) {
+        let since = now();
+        {
+            let mut agents = self.agents.lock().unwrap();
+            let entry = agents
+                .entry(name.to_string())
+                .or_insert_with(|| AgentState {
+                    name: name.to_string(),
+                    specialization: specialization.to_string(),
+                    state: AgentLifecycle::Idle,
+                    since,
+                });
+            entry.state = state.clone();
+            entry.since = since;
+        }
+
+        self.emit(app, name, state, since);
+    }
+
+    /// Records in-flight routing status under `key` (e.g. `"chat"` while a
+    /// message is being dispatched before we know which agent answers it).
+    /// Still emits `agent_state_changed` so the frontend can show activity,
+    /// but `key` is never added to the queryable agent map.
+    pub fn dispatch(&self, app: &AppHandle, key: &str, state: AgentLifecycle) {
+        let since = now();
+        self.dispatch
+            .lock()
+            .unwrap()
+            .insert(key.to_string(), state.clone());
+        self.emit(app, key, state, since);
+    }
+
+    fn emit(&self, app: &AppHandle, name: &str, state: AgentLifecycle, since: f64) {
+        let _ = app.emit(
+            "agent_state_changed",
+            AgentStateChanged {
+                name: name.to_string(),
+                state,
+                since,
+            },
+        );
+    }
+}
+
+fn now() -> f64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs_f64())
+        .unwrap_or(0.0)
+}
@@ -0,0 +1,150 @@
+//! Declarative workload runner: replays a JSON-defined sequence of sidecar
+//! calls and reports per-command timing, so Python-bridge latency can be
+//! tracked across changes.
+
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::sidecar::Sidecar;
+
+#[derive(Debug, Deserialize)]
+struct WorkloadFile {
+    name: String,
+    commands: Vec<WorkloadCommand>,
+    #[serde(default)]
+    results_endpoint: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct WorkloadCommand {
+    method: String,
+    #[serde(default)]
+    params: Value,
+    #[serde(default = "default_repeat")]
+    repeat: u32,
+}
+
+fn default_repeat() -> u32 {
+    1
+}
+
+#[derive(Debug, Serialize)]
+pub struct CommandTiming {
+    method: String,
+    count: u32,
+    min_ms: f64,
+    median_ms: f64,
+    p95_ms: f64,
+    max_ms: f64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct EnvironmentInfo {
+    os: String,
+    cpu_count: usize,
+    python_path: String,
+    python_version: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct WorkloadReport {
+    name: String,
+    environment: EnvironmentInfo,
+    commands: Vec<CommandTiming>,
+    total_duration_ms: f64,
+}
+
+/// Loads the workload at `path`, runs each listed command against `sidecar`
+/// in order (each `repeat` times), and aggregates timing into a
+/// [`WorkloadReport`]. Optionally publishes the report to the workload's
+/// `results_endpoint`.
+pub async fn run(path: &str, sidecar: &Sidecar, python_path: &str) -> Result<WorkloadReport, String> {
+    let raw = std::fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read workload file: {}", e))?;
+    let workload: WorkloadFile = serde_json::from_str(&raw)
+        .map_err(|e| format!("Failed to parse workload file: {}", e))?;
+
+    let started = Instant::now();
+    let mut commands = Vec::with_capacity(workload.commands.len());
+
+    for command in &workload.commands {
+        let repeat = command.repeat.max(1);
+        let mut samples = Vec::with_capacity(repeat as usize);
+        for _ in 0..repeat {
+            let call_started = Instant::now();
+            sidecar.call(&command.method, command.params.clone()).await?;
+            samples.push(call_started.elapsed());
+        }
+        commands.push(summarize(&command.method, samples));
+    }
+
+    let report = WorkloadReport {
+        name: workload.name,
+        environment: detect_environment(python_path),
+        commands,
+        total_duration_ms: started.elapsed().as_secs_f64() * 1000.0,
+    };
+
+    if let Some(endpoint) = &workload.results_endpoint {
+        if let Err(e) = post_report(endpoint, &report).await {
+            log::warn!("Failed to publish workload report to {}: {}", endpoint, e);
+        }
+    }
+
+    Ok(report)
+}
+
+fn summarize(method: &str, mut samples: Vec<Duration>) -> CommandTiming {
+    samples.sort();
+    let to_ms = |d: Duration| d.as_secs_f64() * 1000.0;
+    let percentile = |p: f64| {
+        let idx = ((samples.len() as f64 - 1.0) * p).round() as usize;
+        to_ms(samples[idx])
+    };
+
+    CommandTiming {
+        method: method.to_string(),
+        count: samples.len() as u32,
+        min_ms: samples.first().copied().map(to_ms).unwrap_or(0.0),
+        median_ms: percentile(0.5),
+        p95_ms: percentile(0.95),
+        max_ms: samples.last().copied().map(to_ms).unwrap_or(0.0),
+    }
+}
+
+fn detect_environment(python_path: &str) -> EnvironmentInfo {
+    let python_version = std::process::Command::new(python_path)
+        .arg("--version")
+        .output()
+        .ok()
+        .map(|output| {
+            // Some Python builds print `--version` to stderr rather than stdout.
+            let text = if output.stdout.is_empty() {
+                output.stderr
+            } else {
+                output.stdout
+            };
+            String::from_utf8_lossy(&text).trim().to_string()
+        });
+
+    EnvironmentInfo {
+        os: std::env::consts::OS.to_string(),
+        cpu_count: std::thread::available_parallelism()
+            .map(|n| n.get())
+            .unwrap_or(1),
+        python_path: python_path.to_string(),
+        python_version,
+    }
+}
+
+async fn post_report(endpoint: &str, report: &WorkloadReport) -> Result<(), String> {
+    reqwest::Client::new()
+        .post(endpoint)
+        .json(report)
+        .send()
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(())
+}
@@ -0,0 +1,130 @@
+//! SQLite-backed cache of past analysis runs, so `get_latest_analysis` can
+//! serve a result instantly (and work offline) instead of re-invoking the
+//! clustering engine on every call.
+
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+use rusqlite::{params, Connection, OptionalExtension, Row};
+
+use crate::AnalysisResults;
+
+/// Handle to the `analyses` table. Cheap to clone; every clone shares the
+/// same connection.
+#[derive(Clone)]
+pub struct History {
+    conn: Arc<Mutex<Connection>>,
+}
+
+impl History {
+    /// Opens (creating if needed) the SQLite database at `path` and ensures
+    /// the `analyses` table exists.
+    pub fn open(path: &Path) -> rusqlite::Result<Self> {
+        if let Some(dir) = path.parent() {
+            let _ = std::fs::create_dir_all(dir);
+        }
+
+        let conn = Connection::open(path)?;
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS analyses (
+                analysis_id TEXT PRIMARY KEY,
+                timestamp TEXT NOT NULL,
+                data_summary TEXT NOT NULL,
+                clustering_results TEXT NOT NULL,
+                consensus TEXT NOT NULL,
+                recommendations TEXT NOT NULL,
+                analysis_duration_seconds REAL,
+                error TEXT
+            )",
+            [],
+        )?;
+
+        Ok(Self {
+            conn: Arc::new(Mutex::new(conn)),
+        })
+    }
+
+    /// Inserts or replaces a completed analysis run, keyed by `analysis_id`.
+    pub fn record(&self, analysis: &AnalysisResults) -> rusqlite::Result<()> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "INSERT OR REPLACE INTO analyses
+                (analysis_id, timestamp, data_summary, clustering_results, consensus, recommendations, analysis_duration_seconds, error)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            params![
+                analysis.analysis_id,
+                analysis.timestamp,
+                serde_json::to_string(&analysis.data_summary).unwrap_or_default(),
+                serde_json::to_string(&analysis.clustering_results).unwrap_or_default(),
+                serde_json::to_string(&analysis.consensus).unwrap_or_default(),
+                serde_json::to_string(&analysis.recommendations).unwrap_or_default(),
+                analysis.analysis_duration_seconds,
+                analysis.error,
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// Returns the most recently recorded analysis, if any.
+    pub fn latest(&self) -> rusqlite::Result<Option<AnalysisResults>> {
+        let conn = self.conn.lock().unwrap();
+        conn.query_row(
+            "SELECT * FROM analyses ORDER BY timestamp DESC LIMIT 1",
+            [],
+            row_to_analysis,
+        )
+        .optional()
+    }
+
+    /// Returns up to `limit` rows, newest first, starting at `offset`.
+    pub fn list(&self, limit: u32, offset: u32) -> rusqlite::Result<Vec<AnalysisResults>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn
+            .prepare("SELECT * FROM analyses ORDER BY timestamp DESC LIMIT ?1 OFFSET ?2")?;
+        let rows = stmt.query_map(params![limit, offset], row_to_analysis)?;
+        rows.collect()
+    }
+
+    /// Looks up a single analysis by id.
+    pub fn by_id(&self, analysis_id: &str) -> rusqlite::Result<Option<AnalysisResults>> {
+        let conn = self.conn.lock().unwrap();
+        conn.query_row(
+            "SELECT * FROM analyses WHERE analysis_id = ?1",
+            params![analysis_id],
+            row_to_analysis,
+        )
+        .optional()
+    }
+
+    /// Drops all but the `keep` most recent rows and reclaims disk space.
+    /// Returns the number of rows removed.
+    pub fn clean(&self, keep: u32) -> rusqlite::Result<usize> {
+        let conn = self.conn.lock().unwrap();
+        let removed = conn.execute(
+            "DELETE FROM analyses WHERE analysis_id NOT IN (
+                SELECT analysis_id FROM analyses ORDER BY timestamp DESC LIMIT ?1
+            )",
+            params![keep],
+        )?;
+        conn.execute("VACUUM", [])?;
+        Ok(removed)
+    }
+}
+
+fn row_to_analysis(row: &Row) -> rusqlite::Result<AnalysisResults> {
+    let data_summary: String = row.get("data_summary")?;
+    let clustering_results: String = row.get("clustering_results")?;
+    let consensus: String = row.get("consensus")?;
+    let recommendations: String = row.get("recommendations")?;
+
+    Ok(AnalysisResults {
+        timestamp: row.get("timestamp")?,
+        analysis_id: row.get("analysis_id")?,
+        data_summary: serde_json::from_str(&data_summary).unwrap_or_default(),
+        clustering_results: serde_json::from_str(&clustering_results).unwrap_or_default(),
+        consensus: serde_json::from_str(&consensus).unwrap_or_default(),
+        recommendations: serde_json::from_str(&recommendations).unwrap_or_default(),
+        analysis_duration_seconds: row.get("analysis_duration_seconds")?,
+        error: row.get("error")?,
+    })
+}
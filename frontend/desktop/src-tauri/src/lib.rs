@@ -1,11 +1,25 @@
+mod agent_state;
+mod benchmark;
+mod history;
+mod metrics;
+mod sidecar;
+
 use std::process::Command;
 use serde::{Deserialize, Serialize};
-use tauri::State;
+use serde_json::json;
+use tauri::ipc::Channel;
+use tauri::{AppHandle, State};
 use std::sync::Mutex;
 use tauri::Manager;
 
-#[derive(Debug, Serialize, Deserialize)]
-struct AnalysisResults {
+use agent_state::{AgentLifecycle, AgentRegistry, AgentState};
+use benchmark::WorkloadReport;
+use history::History;
+use metrics::{ProcessMetrics, SystemMonitor};
+use sidecar::Sidecar;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct AnalysisResults {
     timestamp: String,
     analysis_id: String,
     data_summary: serde_json::Value,
@@ -24,6 +38,17 @@ struct SystemMetrics {
     memory_usage: f64,
     cpu_usage: Option<f64>,
     last_analysis: Option<String>,
+    process_specific: Option<ProcessMetrics>,
+}
+
+/// The sidecar-reported fields of `SystemMetrics` that can't be read from
+/// `sysinfo` — events/agents/status come from CelFlow's own state, not the OS.
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct SidecarStatus {
+    events_today: u32,
+    active_agents: u32,
+    clustering_status: String,
+    last_analysis: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -52,171 +77,270 @@ struct ChatSession {
     messages: Vec<ChatMessage>,
 }
 
+/// One chunk of a streamed chat reply, forwarded to the frontend as the
+/// sidecar emits it rather than buffered until the full response is ready.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum ChatStreamEvent {
+    Token { session_id: String, delta: String },
+    SuggestedAction { text: String },
+    Done { confidence: f64 },
+    Error { message: String },
+}
+
 struct AppState {
     python_path: Mutex<String>,
+    sidecar: Sidecar,
+    system: SystemMonitor,
+    agents: AgentRegistry,
 }
 
 #[tauri::command]
-async fn get_latest_analysis(state: State<'_, AppState>) -> Result<AnalysisResults, String> {
-    let python_path = state.python_path.lock().unwrap();
-    
-    // Execute Python script to get latest analysis
-    let output = Command::new(&*python_path)
-        .arg("app/analytics/advanced_clustering_engine.py")
-        .arg("--export-json")
-        .current_dir("../")
-        .output()
-        .map_err(|e| format!("Failed to execute Python script: {}", e))?;
-
-    if !output.status.success() {
-        return Err(format!("Python script failed: {}", String::from_utf8_lossy(&output.stderr)));
+async fn get_latest_analysis(
+    state: State<'_, AppState>,
+    history: State<'_, History>,
+) -> Result<AnalysisResults, String> {
+    if let Some(cached) = history.latest().map_err(|e| e.to_string())? {
+        // Serve the cached row instantly; refresh it in the background so the
+        // next call picks up a newer result without blocking this one.
+        let sidecar = state.sidecar.clone();
+        let history = history.inner().clone();
+        tauri::async_runtime::spawn(async move {
+            match sidecar.call("latest_analysis", json!({})).await {
+                Ok(result) => match serde_json::from_value::<AnalysisResults>(result) {
+                    Ok(analysis) => {
+                        if let Err(e) = history.record(&analysis) {
+                            log::warn!("Failed to record refreshed analysis: {}", e);
+                        }
+                    }
+                    Err(e) => log::warn!("Failed to parse refreshed analysis: {}", e),
+                },
+                Err(e) => log::warn!("Background analysis refresh failed: {}", e),
+            }
+        });
+        return Ok(cached);
+    }
+
+    let result = state.sidecar.call("latest_analysis", json!({})).await?;
+    let analysis: AnalysisResults = serde_json::from_value(result)
+        .map_err(|e| format!("Failed to parse sidecar response: {}", e))?;
+    if let Err(e) = history.record(&analysis) {
+        log::warn!("Failed to record analysis history: {}", e);
     }
+    Ok(analysis)
+}
 
-    let json_str = String::from_utf8_lossy(&output.stdout);
-    serde_json::from_str(&json_str)
-        .map_err(|e| format!("Failed to parse JSON: {}", e))
+#[tauri::command]
+async fn get_analysis_history(
+    history: State<'_, History>,
+    limit: u32,
+    offset: u32,
+) -> Result<Vec<AnalysisResults>, String> {
+    history.list(limit, offset).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn get_analysis_by_id(
+    history: State<'_, History>,
+    id: String,
+) -> Result<AnalysisResults, String> {
+    history
+        .by_id(&id)
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| format!("No analysis found with id {}", id))
+}
+
+#[tauri::command]
+async fn clean_history(history: State<'_, History>, keep: u32) -> Result<usize, String> {
+    history.clean(keep).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn run_workload(state: State<'_, AppState>, path: String) -> Result<WorkloadReport, String> {
+    let python_path = state.python_path.lock().unwrap().clone();
+    benchmark::run(&path, &state.sidecar, &python_path).await
+}
+
+#[tauri::command]
+async fn get_agent_states(state: State<'_, AppState>) -> Result<Vec<AgentState>, String> {
+    Ok(state.agents.snapshot())
 }
 
 #[tauri::command]
-async fn get_system_metrics() -> Result<SystemMetrics, String> {
-    // Mock system metrics for now - in production this would query actual system stats
+async fn get_system_metrics(state: State<'_, AppState>) -> Result<SystemMetrics, String> {
+    let (cpu_usage, memory_usage) = state.system.refresh_global();
+
+    let process_specific = state
+        .sidecar
+        .pid()
+        .and_then(|pid| state.system.refresh_process(pid));
+
+    let status = match state.sidecar.call("system_status", json!({})).await {
+        Ok(result) => serde_json::from_value(result).unwrap_or_default(),
+        Err(e) => {
+            log::warn!("Failed to fetch sidecar status: {}", e);
+            SidecarStatus::default()
+        }
+    };
+
     Ok(SystemMetrics {
-        events_today: 8542,
-        active_agents: 2,
-        clustering_status: "Active".to_string(),
-        memory_usage: 245.6,
-        cpu_usage: Some(12.3),
-        last_analysis: Some("2 minutes ago".to_string()),
+        events_today: status.events_today,
+        active_agents: status.active_agents,
+        clustering_status: status.clustering_status,
+        memory_usage,
+        cpu_usage: Some(cpu_usage),
+        last_analysis: status.last_analysis,
+        process_specific,
     })
 }
 
 #[tauri::command]
-async fn trigger_analysis(state: State<'_, AppState>) -> Result<String, String> {
-    let python_path = state.python_path.lock().unwrap();
-    
-    // Execute Python script to trigger new analysis
-    let output = Command::new(&*python_path)
-        .arg("app/analytics/advanced_clustering_engine.py")
-        .arg("--force-analysis")
-        .current_dir("../")
-        .output()
-        .map_err(|e| format!("Failed to execute Python script: {}", e))?;
-
-    if !output.status.success() {
-        return Err(format!("Analysis failed: {}", String::from_utf8_lossy(&output.stderr)));
-    }
+async fn trigger_analysis(
+    state: State<'_, AppState>,
+    history: State<'_, History>,
+    app: AppHandle,
+) -> Result<String, String> {
+    state
+        .agents
+        .dispatch(&app, "clustering", AgentLifecycle::Analyzing);
+
+    let result = state.sidecar.call("trigger_analysis", json!({})).await;
+    let result = match result {
+        Ok(result) => result,
+        Err(e) => {
+            state.agents.dispatch(
+                &app,
+                "clustering",
+                AgentLifecycle::Error { reason: e.clone() },
+            );
+            return Err(e);
+        }
+    };
 
+    if let Ok(analysis) = serde_json::from_value::<AnalysisResults>(result) {
+        if let Err(e) = history.record(&analysis) {
+            log::warn!("Failed to record analysis history: {}", e);
+        }
+    }
+    state
+        .agents
+        .dispatch(&app, "clustering", AgentLifecycle::Idle);
     Ok("Analysis triggered successfully".to_string())
 }
 
 #[tauri::command]
-async fn start_chat_session(state: State<'_, AppState>) -> Result<String, String> {
-    let python_path = state.python_path.lock().unwrap();
-    
-    // Execute Python script to start a chat session
-    let output = Command::new(&*python_path)
-        .arg("-c")
-        .arg(r#"
-import asyncio
-from backend.app.system.system_integration import CelFlowSystemIntegration
-
-async def start_session():
-    system = CelFlowSystemIntegration()
-    await system.initialize()
-    result = await system.chat_with_agents("", None)
-    print(result.get("session_id", ""))
-
-asyncio.run(start_session())
-        "#)
-        .current_dir("../")
-        .output()
-        .map_err(|e| format!("Failed to start chat session: {}", e))?;
-
-    if !output.status.success() {
-        return Err(format!("Failed to start chat session: {}", String::from_utf8_lossy(&output.stderr)));
+async fn start_chat_session(state: State<'_, AppState>, app: AppHandle) -> Result<String, String> {
+    state
+        .agents
+        .dispatch(&app, "chat", AgentLifecycle::Initializing);
+
+    let result = state.sidecar.call("start_chat_session", json!({})).await;
+    match result {
+        Ok(result) => {
+            state.agents.dispatch(&app, "chat", AgentLifecycle::Idle);
+            serde_json::from_value(result).map_err(|e| format!("Failed to parse sidecar response: {}", e))
+        }
+        Err(e) => {
+            state
+                .agents
+                .dispatch(&app, "chat", AgentLifecycle::Error { reason: e.clone() });
+            Err(e)
+        }
     }
+}
+
+#[tauri::command]
+async fn send_chat_message(
+    state: State<'_, AppState>,
+    app: AppHandle,
+    message: String,
+    session_id: String,
+) -> Result<ChatResponse, String> {
+    state
+        .agents
+        .dispatch(&app, "chat", AgentLifecycle::Responding);
+
+    let result = state
+        .sidecar
+        .call("chat_send", json!({ "message": message, "session_id": session_id }))
+        .await;
 
-    let session_id = String::from_utf8_lossy(&output.stdout).trim().to_string();
-    Ok(session_id)
+    match result {
+        Ok(result) => {
+            let response: ChatResponse = serde_json::from_value(result)
+                .map_err(|e| format!("Failed to parse sidecar response: {}", e))?;
+            // Clear the in-flight "chat" dispatch marker and record the
+            // agent that actually handled the message under its own
+            // queryable entry, so neither is left stuck in `Responding`.
+            state.agents.dispatch(&app, "chat", AgentLifecycle::Idle);
+            state.agents.transition(
+                &app,
+                &response.response.agent_name,
+                &response.response.specialization,
+                AgentLifecycle::Idle,
+            );
+            Ok(response)
+        }
+        Err(e) => {
+            state
+                .agents
+                .dispatch(&app, "chat", AgentLifecycle::Error { reason: e.clone() });
+            Err(e)
+        }
+    }
 }
 
 #[tauri::command]
-async fn send_chat_message(state: State<'_, AppState>, message: String, session_id: String) -> Result<ChatResponse, String> {
-    let python_path = state.python_path.lock().unwrap();
-    
-    // Execute Python script to send a chat message
-    let output = Command::new(&*python_path)
-        .arg("-c")
-        .arg(format!(r#"
-import asyncio
-import json
-from backend.app.system.system_integration import CelFlowSystemIntegration
-
-async def send_message():
-    system = CelFlowSystemIntegration()
-    await system.initialize()
-    result = await system.chat_with_agents("{}", "{}")
-    print(json.dumps(result))
-
-asyncio.run(send_message())
-        "#, message.replace("\"", "\\\""), session_id))
-        .current_dir("../")
-        .output()
-        .map_err(|e| format!("Failed to send message: {}", e))?;
-
-    if !output.status.success() {
-        return Err(format!("Failed to send message: {}", String::from_utf8_lossy(&output.stderr)));
+async fn send_chat_message_streaming(
+    state: State<'_, AppState>,
+    message: String,
+    session_id: String,
+    channel: Channel<ChatStreamEvent>,
+) -> Result<(), String> {
+    let mut stream = state
+        .sidecar
+        .call_streaming(
+            "chat_send_stream",
+            json!({ "message": message, "session_id": session_id }),
+        )
+        .await?;
+
+    while let Some(chunk) = stream.recv().await {
+        let event: ChatStreamEvent = serde_json::from_value(chunk)
+            .map_err(|e| format!("Failed to parse chat stream event: {}", e))?;
+        let is_terminal = matches!(event, ChatStreamEvent::Done { .. } | ChatStreamEvent::Error { .. });
+        channel
+            .send(event)
+            .map_err(|e| format!("Failed to forward chat stream event: {}", e))?;
+        if is_terminal {
+            break;
+        }
     }
 
-    let json_str = String::from_utf8_lossy(&output.stdout);
-    serde_json::from_str(&json_str)
-        .map_err(|e| format!("Failed to parse response: {}", e))
+    Ok(())
 }
 
 #[tauri::command]
 async fn get_chat_history(state: State<'_, AppState>, session_id: String) -> Result<ChatSession, String> {
-    let python_path = state.python_path.lock().unwrap();
-    
-    // Execute Python script to get chat history
-    let output = Command::new(&*python_path)
-        .arg("-c")
-        .arg(format!(r#"
-import asyncio
-import json
-from backend.app.system.system_integration import CelFlowSystemIntegration
-
-async def get_history():
-    system = CelFlowSystemIntegration()
-    await system.initialize()
-    if system.agent_interface:
-        history = system.agent_interface.get_session_history("{}")
-        print(json.dumps(history))
-    else:
-        print(json.dumps({{}}))
-
-asyncio.run(get_history())
-        "#, session_id))
-        .current_dir("../")
-        .output()
-        .map_err(|e| format!("Failed to get chat history: {}", e))?;
-
-    if !output.status.success() {
-        return Err(format!("Failed to get chat history: {}", String::from_utf8_lossy(&output.stderr)));
-    }
-
-    let json_str = String::from_utf8_lossy(&output.stdout);
-    serde_json::from_str(&json_str)
-        .map_err(|e| format!("Failed to parse chat history: {}", e))
+    let result = state
+        .sidecar
+        .call("chat_history", json!({ "session_id": session_id }))
+        .await?;
+    serde_json::from_value(result).map_err(|e| format!("Failed to parse sidecar response: {}", e))
 }
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
     // Detect Python path
     let python_path = detect_python_path();
-    
+    let sidecar = Sidecar::spawn(python_path.clone(), "app/sidecar.py".to_string());
+
     tauri::Builder::default()
         .manage(AppState {
             python_path: Mutex::new(python_path),
+            sidecar,
+            system: SystemMonitor::new(),
+            agents: AgentRegistry::new(),
         })
         .invoke_handler(tauri::generate_handler![
             get_latest_analysis,
@@ -224,7 +348,13 @@ pub fn run() {
             trigger_analysis,
             start_chat_session,
             send_chat_message,
-            get_chat_history
+            send_chat_message_streaming,
+            get_chat_history,
+            get_analysis_history,
+            get_analysis_by_id,
+            clean_history,
+            run_workload,
+            get_agent_states
         ])
         .setup(|app| {
             if cfg!(debug_assertions) {
@@ -234,7 +364,10 @@ pub fn run() {
                         .build(),
                 )?;
             }
-            
+
+            let history_path = app.path().app_data_dir()?.join("analysis_history.sqlite");
+            app.manage(History::open(&history_path)?);
+
             // Show the main window immediately on startup
             if let Some(window) = app.get_webview_window("main") {
                 window.show().expect("Failed to show window");
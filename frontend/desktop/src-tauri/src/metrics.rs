@@ -0,0 +1,68 @@
+//! Wraps `sysinfo` so `get_system_metrics` reports real numbers instead of a
+//! fixed mock, and can report the sidecar's own usage separately from the
+//! system-wide load.
+
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+use sysinfo::{Pid, ProcessesToUpdate, System};
+
+/// CPU/memory usage for a single process, used to isolate the sidecar's own
+/// footprint from the system-wide numbers.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProcessMetrics {
+    pub cpu_usage: f32,
+    pub memory_usage_mb: f64,
+}
+
+/// Holds the `sysinfo::System` snapshot backing `get_system_metrics`.
+pub struct SystemMonitor {
+    system: Mutex<System>,
+}
+
+impl SystemMonitor {
+    pub fn new() -> Self {
+        Self {
+            system: Mutex::new(System::new_all()),
+        }
+    }
+
+    /// Refreshes the global CPU counter and this process's own memory
+    /// footprint, returning `(cpu_usage_percent, own_memory_usage_mb)`.
+    /// `cpu_usage` is system-wide (there's no cheap per-process CPU sample
+    /// without waiting out sysinfo's minimum refresh interval), but
+    /// `memory_usage` is this app's own RSS, not the whole system's, so it
+    /// actually reports CelFlow's footprint rather than doubling up with
+    /// system-wide load.
+    pub fn refresh_global(&self) -> (f64, f64) {
+        let mut system = self.system.lock().unwrap();
+        system.refresh_cpu_usage();
+        let cpu_usage = system.global_cpu_usage() as f64;
+
+        let memory_usage_mb = sysinfo::get_current_pid()
+            .ok()
+            .map(|pid| {
+                system.refresh_processes(ProcessesToUpdate::Some(&[pid]), true);
+                system
+                    .process(pid)
+                    .map(|process| process.memory() as f64 / 1024.0 / 1024.0)
+                    .unwrap_or(0.0)
+            })
+            .unwrap_or(0.0);
+
+        (cpu_usage, memory_usage_mb)
+    }
+
+    /// Refreshes and reports CPU/memory for a single process (the sidecar's
+    /// PID), so the dashboard can distinguish CelFlow's own load from
+    /// system-wide load.
+    pub fn refresh_process(&self, pid: u32) -> Option<ProcessMetrics> {
+        let mut system = self.system.lock().unwrap();
+        let pid = Pid::from_u32(pid);
+        system.refresh_processes(ProcessesToUpdate::Some(&[pid]), true);
+        system.process(pid).map(|process| ProcessMetrics {
+            cpu_usage: process.cpu_usage(),
+            memory_usage_mb: process.memory() as f64 / 1024.0 / 1024.0,
+        })
+    }
+}
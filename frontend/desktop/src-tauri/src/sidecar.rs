@@ -0,0 +1,256 @@
+//! Long-lived Python worker process speaking newline-delimited JSON-RPC over
+//! stdin/stdout, so commands stop paying a fresh interpreter + model load on
+//! every invocation.
+
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader, Write};
+use std::process::{ChildStdin, Command, Stdio};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tokio::sync::{mpsc, oneshot};
+
+#[derive(Debug, Serialize)]
+struct SidecarRequest {
+    id: u64,
+    method: String,
+    params: Value,
+}
+
+#[derive(Debug, Deserialize)]
+struct SidecarResponse {
+    id: u64,
+    result: Option<Value>,
+    error: Option<String>,
+    /// Present on streaming responses; `true` marks the final chunk for this
+    /// request id, after which the subscription is dropped.
+    #[serde(default)]
+    done: Option<bool>,
+}
+
+type PendingMap = Arc<Mutex<HashMap<u64, oneshot::Sender<SidecarResponse>>>>;
+type StreamMap = Arc<Mutex<HashMap<u64, mpsc::UnboundedSender<SidecarResponse>>>>;
+
+/// Handle to the persistent `app/sidecar.py` worker. Cloning is cheap; every
+/// clone shares the same stdin pipe and pending-request table.
+#[derive(Clone)]
+pub struct Sidecar {
+    stdin: Arc<Mutex<Option<ChildStdin>>>,
+    pending: PendingMap,
+    streams: StreamMap,
+    next_id: Arc<AtomicU64>,
+    pid: Arc<Mutex<Option<u32>>>,
+}
+
+impl Sidecar {
+    /// Spawns the worker and starts the reader/supervisor thread that keeps
+    /// it alive for the lifetime of the app.
+    pub fn spawn(python_path: String, script: String) -> Self {
+        let stdin = Arc::new(Mutex::new(None));
+        let pending: PendingMap = Arc::new(Mutex::new(HashMap::new()));
+        let streams: StreamMap = Arc::new(Mutex::new(HashMap::new()));
+        let pid = Arc::new(Mutex::new(None));
+
+        {
+            let stdin = stdin.clone();
+            let pending = pending.clone();
+            let streams = streams.clone();
+            let pid = pid.clone();
+            std::thread::spawn(move || run_supervisor(python_path, script, stdin, pending, streams, pid));
+        }
+
+        Self {
+            stdin,
+            pending,
+            streams,
+            next_id: Arc::new(AtomicU64::new(1)),
+            pid,
+        }
+    }
+
+    /// The worker's current OS process id, if it is up right now.
+    pub fn pid(&self) -> Option<u32> {
+        *self.pid.lock().unwrap()
+    }
+
+    /// Sends `method`/`params` to the worker and awaits the matching
+    /// response by request id.
+    pub async fn call(&self, method: &str, params: Value) -> Result<Value, String> {
+        let id = self.next_id.fetch_add(1, Ordering::SeqCst);
+        let (tx, rx) = oneshot::channel();
+        self.pending.lock().unwrap().insert(id, tx);
+
+        if let Err(e) = self.write_request(id, method, params) {
+            self.pending.lock().unwrap().remove(&id);
+            return Err(e);
+        }
+
+        let response = rx
+            .await
+            .map_err(|_| "Sidecar crashed before responding".to_string())?;
+        match response.error {
+            Some(err) => Err(err),
+            None => response
+                .result
+                .ok_or_else(|| "Sidecar response missing result".to_string()),
+        }
+    }
+
+    /// Sends `method`/`params` and returns a channel that receives one
+    /// decoded chunk per line the worker streams back, until it sends a
+    /// chunk with `done: true`.
+    pub async fn call_streaming(
+        &self,
+        method: &str,
+        params: Value,
+    ) -> Result<mpsc::UnboundedReceiver<Value>, String> {
+        let id = self.next_id.fetch_add(1, Ordering::SeqCst);
+        let (tx, mut raw_rx) = mpsc::unbounded_channel();
+        self.streams.lock().unwrap().insert(id, tx);
+
+        if let Err(e) = self.write_request(id, method, params) {
+            self.streams.lock().unwrap().remove(&id);
+            return Err(e);
+        }
+
+        let (out_tx, out_rx) = mpsc::unbounded_channel();
+        tokio::spawn(async move {
+            while let Some(response) = raw_rx.recv().await {
+                match response.error {
+                    Some(err) => {
+                        let _ = out_tx.send(serde_json::json!({ "type": "error", "message": err }));
+                        break;
+                    }
+                    None => {
+                        if let Some(result) = response.result {
+                            let _ = out_tx.send(result);
+                        }
+                    }
+                }
+            }
+        });
+
+        Ok(out_rx)
+    }
+
+    fn write_request(&self, id: u64, method: &str, params: Value) -> Result<(), String> {
+        let request = SidecarRequest {
+            id,
+            method: method.to_string(),
+            params,
+        };
+        let mut line = serde_json::to_string(&request)
+            .map_err(|e| format!("Failed to encode sidecar request: {}", e))?;
+        line.push('\n');
+
+        let mut stdin = self.stdin.lock().unwrap();
+        let stdin = stdin
+            .as_mut()
+            .ok_or_else(|| "Sidecar is not connected".to_string())?;
+        stdin
+            .write_all(line.as_bytes())
+            .map_err(|e| format!("Failed to write to sidecar: {}", e))?;
+        stdin
+            .flush()
+            .map_err(|e| format!("Failed to flush sidecar stdin: {}", e))
+    }
+}
+
+/// Keeps the worker running: spawns it, wires up a reader thread for its
+/// stdout, waits for it to exit, fails any calls still in flight, then loops
+/// to respawn. Runs for the lifetime of the app.
+fn run_supervisor(
+    python_path: String,
+    script: String,
+    stdin_slot: Arc<Mutex<Option<ChildStdin>>>,
+    pending: PendingMap,
+    streams: StreamMap,
+    pid_slot: Arc<Mutex<Option<u32>>>,
+) {
+    loop {
+        let mut child = match Command::new(&python_path)
+            .arg(&script)
+            .current_dir("../")
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::inherit())
+            .spawn()
+        {
+            Ok(child) => child,
+            Err(e) => {
+                log::error!("Failed to spawn sidecar ({}): {}", script, e);
+                std::thread::sleep(Duration::from_secs(1));
+                continue;
+            }
+        };
+
+        let child_stdin = child.stdin.take().expect("sidecar stdin not piped");
+        let stdout = child.stdout.take().expect("sidecar stdout not piped");
+        *stdin_slot.lock().unwrap() = Some(child_stdin);
+        *pid_slot.lock().unwrap() = Some(child.id());
+
+        let reader_pending = pending.clone();
+        let reader_streams = streams.clone();
+        let reader = std::thread::spawn(move || {
+            for line in BufReader::new(stdout).lines() {
+                let Ok(line) = line else { break };
+                if line.trim().is_empty() {
+                    continue;
+                }
+                match serde_json::from_str::<SidecarResponse>(&line) {
+                    Ok(response) => route_response(&reader_pending, &reader_streams, response),
+                    Err(e) => log::error!("Sidecar sent malformed response `{}`: {}", line, e),
+                }
+            }
+        });
+
+        let status = child.wait();
+        let _ = reader.join();
+        *stdin_slot.lock().unwrap() = None;
+        *pid_slot.lock().unwrap() = None;
+
+        log::error!("Sidecar worker exited ({:?}); restarting", status);
+        let crash = || SidecarResponse {
+            id: 0,
+            result: None,
+            error: Some("Sidecar crashed before responding".to_string()),
+            done: Some(true),
+        };
+        for (_, tx) in pending.lock().unwrap().drain() {
+            let _ = tx.send(crash());
+        }
+        for (_, tx) in streams.lock().unwrap().drain() {
+            let _ = tx.send(crash());
+        }
+
+        // The worker exited on its own (not just a failed spawn) — back off
+        // before respawning so a crash loop (bad script path, import error)
+        // doesn't pin the supervisor thread fork/exec-ing as fast as the OS
+        // allows and spamming logs.
+        std::thread::sleep(Duration::from_secs(1));
+    }
+}
+
+/// Dispatches one decoded response to whichever table is waiting on its id:
+/// a single-shot [`call`](Sidecar::call) or a [`call_streaming`](Sidecar::call_streaming)
+/// subscription. Streaming subscriptions are removed once a chunk with
+/// `done: true` arrives.
+fn route_response(pending: &PendingMap, streams: &StreamMap, response: SidecarResponse) {
+    if let Some(tx) = pending.lock().unwrap().remove(&response.id) {
+        let _ = tx.send(response);
+        return;
+    }
+
+    let mut streams = streams.lock().unwrap();
+    let id = response.id;
+    if let Some(tx) = streams.get(&id) {
+        let done = response.done.unwrap_or(false) || response.error.is_some();
+        let _ = tx.send(response);
+        if done {
+            streams.remove(&id);
+        }
+    }
+}